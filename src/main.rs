@@ -14,9 +14,11 @@ use tracing_subscriber;
 
 use crate::auth::{AuthMechanism, OAuthClientCredentialsOptions, TlsOptions};
 use crate::routes::{health, metrics, SharedState};
+use crate::tls::{ServerTlsOptions, ServerTlsState};
 
 mod auth;
 mod routes;
+mod tls;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -76,6 +78,22 @@ struct Args {
     /// Filepath to the TLS key
     #[arg(long, env = "TLS_KEY_FILE")]
     key_file: Option<String>,
+
+    /// Contents of the server's TLS certificate chain, for inbound TLS termination
+    #[arg(long, env = "SERVER_TLS_CERT")]
+    server_cert: Option<String>,
+
+    /// Filepath to the server's TLS certificate chain
+    #[arg(long, env = "SERVER_TLS_CERT_FILE")]
+    server_cert_file: Option<String>,
+
+    /// Contents of the server's TLS private key
+    #[arg(long, env = "SERVER_TLS_KEY")]
+    server_key: Option<String>,
+
+    /// Filepath to the server's TLS private key
+    #[arg(long, env = "SERVER_TLS_KEY_FILE")]
+    server_key_file: Option<String>,
 }
 
 /// Determine the authentication method to use based on the given arguments.
@@ -137,9 +155,37 @@ fn determine_auth(args: Args) -> AuthMechanism {
     }
 }
 
+/// Determine whether inbound TLS termination is configured and, if so, build
+/// the server TLS state for it.
+fn determine_server_tls(
+    cert: Option<String>,
+    cert_file: Option<String>,
+    key: Option<String>,
+    key_file: Option<String>,
+) -> Option<ServerTlsState> {
+    match (cert, cert_file, key, key_file) {
+        (None, None, None, None) => None,
+        (Some(cert), None, Some(key), None) => {
+            info!("Inbound TLS termination configured.");
+            Some(
+                ServerTlsState::new(ServerTlsOptions { cert, key })
+                    .expect("Invalid server TLS configuration."),
+            )
+        }
+        (None, Some(cert_file), None, Some(key_file)) => {
+            info!("Inbound TLS termination configured.");
+            Some(
+                ServerTlsState::new(ServerTlsOptions::from_files(cert_file, key_file))
+                    .expect("Invalid server TLS configuration."),
+            )
+        }
+        _ => panic!("Invalid arguments"),
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     // Configure tracing
     tracing_subscriber::fmt::init();
@@ -149,6 +195,14 @@ async fn main() {
     let addr = SocketAddr::from((host, args.port));
     let endpoint = args.endpoint.clone();
 
+    // Determine whether inbound TLS termination is configured
+    let server_tls = determine_server_tls(
+        args.server_cert.take(),
+        args.server_cert_file.take(),
+        args.server_key.take(),
+        args.server_key_file.take(),
+    );
+
     // Determine the auth mechanism to use
     let auth = determine_auth(args);
 
@@ -176,8 +230,49 @@ async fn main() {
 
     // Start the server
     info!("Listening on {}", addr);
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+    match server_tls {
+        Some(server_tls) => serve_tls(addr, app, server_tls).await,
+        None => axum::Server::bind(&addr)
+            .serve(app.into_make_service())
+            .await
+            .unwrap(),
+    }
+}
+
+/// Accept connections on `addr`, terminate TLS on each using `server_tls`,
+/// and hand the resulting plaintext stream off to the axum service.
+async fn serve_tls(addr: SocketAddr, app: Router, server_tls: ServerTlsState) {
+    let listener = tokio::net::TcpListener::bind(addr)
         .await
-        .unwrap();
+        .expect("Failed to bind address.");
+    let acceptor = server_tls.acceptor();
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                // Under e.g. fd exhaustion, `accept` can fail repeatedly in a
+                // tight loop; back off briefly instead of spinning on it.
+                tracing::error!("Failed to accept connection: {}", e);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        tokio::spawn(async move {
+            match acceptor.accept(stream).await {
+                Ok(stream) => {
+                    if let Err(e) = hyper::server::conn::Http::new()
+                        .serve_connection(stream, app)
+                        .await
+                    {
+                        tracing::error!("Error serving connection: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("TLS handshake failed: {}", e),
+            }
+        });
+    }
 }